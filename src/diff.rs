@@ -0,0 +1,485 @@
+use std::collections::{HashMap, HashSet};
+
+use {VirtualDom, VirtualElement, VirtualNode};
+
+/// A single mutation to apply to a rendered tree to bring it in line with a
+/// newer `VirtualDom`. Every patch carries the `path` of child indices that
+/// locates the node it acts on, walking down from the root.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Patch {
+    /// Replace the node at `path` wholesale because its shape changed.
+    ReplaceNode { path: Vec<usize>, node: VirtualNode },
+    /// Add or overwrite an attribute on the element at `path`.
+    SetAttribute { path: Vec<usize>, key: String, value: String },
+    /// Drop an attribute that no longer exists on the element at `path`.
+    RemoveAttribute { path: Vec<usize>, key: String },
+    /// Replace the contents of the text node at `path`.
+    SetText { path: Vec<usize>, text: String },
+    /// Append a new child to the element at `path`.
+    AppendChild { path: Vec<usize>, node: VirtualNode },
+    /// Remove the single child at `index` under the element at `path`.
+    RemoveChild { path: Vec<usize>, index: usize },
+    /// Insert a new keyed child at `index` under the element at `path`.
+    InsertChild { path: Vec<usize>, index: usize, node: VirtualNode },
+    /// Move the keyed child at `from` to position `to` under the element at `path`.
+    MoveChild { path: Vec<usize>, from: usize, to: usize },
+    /// Drop every child past `len` under the element at `path`.
+    Truncate { path: Vec<usize>, len: usize },
+}
+
+/// Compute the patches that turn `old` into `new`, walking both trees in
+/// lockstep from the root.
+pub fn diff(old: &VirtualNode, new: &VirtualNode) -> Vec<Patch> {
+    let mut patches = Vec::new();
+    diff_node(old, new, Vec::new(), &mut patches);
+    patches
+}
+
+/// Diff two fragments by zipping their root slices, treating the `VirtualDom`
+/// itself as the (path-less) parent of its roots.
+pub fn diff_dom(old: &VirtualDom, new: &VirtualDom) -> Vec<Patch> {
+    let mut patches = Vec::new();
+    diff_children(&old.0, &new.0, Vec::new(), &mut patches);
+    patches
+}
+
+fn diff_node(old: &VirtualNode, new: &VirtualNode, path: Vec<usize>, patches: &mut Vec<Patch>) {
+    match (old, new) {
+        (VirtualNode::Element(old_el), VirtualNode::Element(new_el)) => {
+            if old_el.name != new_el.name {
+                patches.push(Patch::ReplaceNode { path, node: new.clone() });
+                return;
+            }
+            diff_element(old_el, new_el, path, patches);
+        }
+        (VirtualNode::Text(old_text), VirtualNode::Text(new_text)) => {
+            if old_text != new_text {
+                patches.push(Patch::SetText { path, text: new_text.clone() });
+            }
+        }
+        _ => patches.push(Patch::ReplaceNode { path, node: new.clone() }),
+    }
+}
+
+fn diff_element(old: &VirtualElement, new: &VirtualElement, path: Vec<usize>, patches: &mut Vec<Patch>) {
+    for (key, value) in &new.attributes {
+        if old.attributes.get(key) != Some(value) {
+            patches.push(Patch::SetAttribute {
+                path: path.clone(),
+                key: key.clone(),
+                value: value.clone(),
+            });
+        }
+    }
+    for key in old.attributes.keys() {
+        if !new.attributes.contains_key(key) {
+            patches.push(Patch::RemoveAttribute { path: path.clone(), key: key.clone() });
+        }
+    }
+    diff_children(&old.child_nodes, &new.child_nodes, path, patches);
+}
+
+fn diff_children(old: &[VirtualNode], new: &[VirtualNode], path: Vec<usize>, patches: &mut Vec<Patch>) {
+    // When every child on both sides carries a key we can reconcile by
+    // identity and emit moves rather than reshuffling the list positionally.
+    if !old.is_empty() && !new.is_empty() && all_keyed(old) && all_keyed(new) {
+        diff_keyed_children(old, new, path, patches);
+        return;
+    }
+
+    let common = old.len().min(new.len());
+    for index in 0..common {
+        let mut child_path = path.clone();
+        child_path.push(index);
+        diff_node(&old[index], &new[index], child_path, patches);
+    }
+    if new.len() > old.len() {
+        for node in &new[old.len()..] {
+            patches.push(Patch::AppendChild { path: path.clone(), node: node.clone() });
+        }
+    } else if old.len() > new.len() {
+        let len = new.len();
+        patches.push(Patch::Truncate { path, len });
+    }
+}
+
+/// True when every node in the slice is an element carrying a `key`.
+fn all_keyed(nodes: &[VirtualNode]) -> bool {
+    nodes.iter().all(|node| match node {
+        VirtualNode::Element(el) => el.key.is_some(),
+        VirtualNode::Text(_) => false,
+    })
+}
+
+fn key_of(node: &VirtualNode) -> &str {
+    match node {
+        VirtualNode::Element(el) => el.key.as_deref().unwrap_or(""),
+        VirtualNode::Text(_) => "",
+    }
+}
+
+/// Reconcile two fully-keyed sibling lists: remove keys that disappeared,
+/// insert brand-new keys, move only the children that fall off the longest
+/// increasing subsequence of their old positions, and recurse into the ones
+/// that survived by key so their content edits are not lost.
+///
+/// Every patch applies **in emission order to a single working list** that
+/// starts as the old child list and ends as the new one, so the index spaces
+/// stay consistent throughout:
+///
+/// 1. `RemoveChild` patches come first, highest index first, shrinking the
+///    working list to the surviving children in their old order.
+/// 2. `MoveChild`/`InsertChild` patches then run right to left, each placing
+///    its node immediately before the anchor — the child from the next new
+///    position, which is already settled — so an anchored child never has to
+///    move. Every `from`/`to`/`index` is a live index into the working list at
+///    the moment that patch applies, computed against the anchor's current
+///    position, so reversals and rotations stay consistent.
+/// 3. Content patches for the survivors come last, addressed by their final
+///    position, so they land on nodes already moved into place.
+fn diff_keyed_children(old: &[VirtualNode], new: &[VirtualNode], path: Vec<usize>, patches: &mut Vec<Patch>) {
+    let mut old_index_for_key: HashMap<&str, usize> = HashMap::new();
+    for (index, node) in old.iter().enumerate() {
+        old_index_for_key.insert(key_of(node), index);
+    }
+
+    // 1. Drop the keys that disappeared, highest index first so each index is
+    // still valid against the shrinking working list.
+    let new_keys: HashSet<&str> = new.iter().map(key_of).collect();
+    for index in (0..old.len()).rev() {
+        if !new_keys.contains(key_of(&old[index])) {
+            patches.push(Patch::RemoveChild { path: path.clone(), index });
+        }
+    }
+
+    // The working list after removals: surviving children, keyed, in old order.
+    let mut working: Vec<&str> = old
+        .iter()
+        .map(key_of)
+        .filter(|key| new_keys.contains(key))
+        .collect();
+    let working_pos: HashMap<&str, usize> =
+        working.iter().enumerate().map(|(pos, &key)| (key, pos)).collect();
+
+    // For each new child, its slot in the post-removal working list, or `None`
+    // when it is brand new. The longest increasing run of these slots is the
+    // set of children already in the right relative order, which never move.
+    let source: Vec<Option<usize>> =
+        new.iter().map(|node| working_pos.get(key_of(node)).cloned()).collect();
+    let anchored = longest_increasing_subsequence(&source);
+
+    // 2. Walk the new order right to left, placing each non-anchored survivor
+    // and each brand-new child immediately before the already-settled anchor.
+    let mut anchor: Option<&str> = None;
+    for to in (0..new.len()).rev() {
+        let key = key_of(&new[to]);
+        let anchor_index = match anchor {
+            Some(anchor_key) => working.iter().position(|&k| k == anchor_key).unwrap(),
+            None => working.len(),
+        };
+        match source[to] {
+            None => {
+                patches.push(Patch::InsertChild {
+                    path: path.clone(),
+                    index: anchor_index,
+                    node: new[to].clone(),
+                });
+                working.insert(anchor_index, key);
+            }
+            Some(_) if anchored.contains(&to) => {}
+            Some(_) => {
+                let from = working.iter().position(|&k| k == key).unwrap();
+                working.remove(from);
+                let target = if from < anchor_index { anchor_index - 1 } else { anchor_index };
+                working.insert(target, key);
+                patches.push(Patch::MoveChild { path: path.clone(), from, to: target });
+            }
+        }
+        anchor = Some(key);
+    }
+
+    // 3. Diff the surviving children in place, addressed by their final slot.
+    for to in 0..new.len() {
+        if source[to].is_some() {
+            let old_index = old_index_for_key[key_of(&new[to])];
+            let mut child_path = path.clone();
+            child_path.push(to);
+            diff_node(&old[old_index], &new[to], child_path, patches);
+        }
+    }
+}
+
+/// Return the set of positions in `source` whose (present) old indices lie on
+/// a longest strictly-increasing subsequence; these children keep their place.
+fn longest_increasing_subsequence(source: &[Option<usize>]) -> HashSet<usize> {
+    let present: Vec<usize> = (0..source.len()).filter(|&i| source[i].is_some()).collect();
+    let values: Vec<usize> = present.iter().map(|&i| source[i].unwrap()).collect();
+
+    let mut stable = HashSet::new();
+    if values.is_empty() {
+        return stable;
+    }
+
+    let mut length = vec![1usize; values.len()];
+    let mut previous: Vec<Option<usize>> = vec![None; values.len()];
+    let mut best = 0;
+    for i in 0..values.len() {
+        for j in 0..i {
+            if values[j] < values[i] && length[j] + 1 > length[i] {
+                length[i] = length[j] + 1;
+                previous[i] = Some(j);
+            }
+        }
+        if length[i] > length[best] {
+            best = i;
+        }
+    }
+
+    let mut cursor = Some(best);
+    while let Some(i) = cursor {
+        stable.insert(present[i]);
+        cursor = previous[i];
+    }
+    stable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {VirtualDom, VirtualElement, VirtualNode};
+
+    fn el(name: &str) -> VirtualElement {
+        let mut el = VirtualElement::new();
+        el.name = name.to_string();
+        el
+    }
+
+    #[test]
+    fn diff_identical_trees_is_empty() {
+        let node = VirtualNode::Element(el("div"));
+        assert_eq!(Vec::<Patch>::new(), diff(&node, &node));
+    }
+
+    #[test]
+    fn diff_replaces_on_name_change() {
+        let old = VirtualNode::Element(el("div"));
+        let new = VirtualNode::Element(el("span"));
+        assert_eq!(
+            vec![Patch::ReplaceNode { path: vec![], node: new.clone() }],
+            diff(&old, &new)
+        );
+    }
+
+    #[test]
+    fn diff_replaces_across_variants() {
+        let old = VirtualNode::Element(el("div"));
+        let new = VirtualNode::Text("hi".into());
+        assert_eq!(
+            vec![Patch::ReplaceNode { path: vec![], node: new.clone() }],
+            diff(&old, &new)
+        );
+    }
+
+    #[test]
+    fn diff_attributes() {
+        let mut old_el = el("div");
+        old_el.attributes.insert("class".into(), "a".into());
+        old_el.attributes.insert("id".into(), "main".into());
+        let mut new_el = el("div");
+        new_el.attributes.insert("class".into(), "b".into());
+
+        let patches = diff(&VirtualNode::Element(old_el), &VirtualNode::Element(new_el));
+        assert!(patches.contains(&Patch::SetAttribute {
+            path: vec![],
+            key: "class".into(),
+            value: "b".into(),
+        }));
+        assert!(patches.contains(&Patch::RemoveAttribute { path: vec![], key: "id".into() }));
+    }
+
+    #[test]
+    fn diff_text_child() {
+        let mut old_el = el("p");
+        old_el.child_nodes.push(VirtualNode::Text("old".into()));
+        let mut new_el = el("p");
+        new_el.child_nodes.push(VirtualNode::Text("new".into()));
+        assert_eq!(
+            vec![Patch::SetText { path: vec![0], text: "new".into() }],
+            diff(&VirtualNode::Element(old_el), &VirtualNode::Element(new_el))
+        );
+    }
+
+    #[test]
+    fn diff_appends_and_truncates_children() {
+        let mut old_el = el("ul");
+        old_el.child_nodes.push(VirtualNode::Element(el("li")));
+        old_el.child_nodes.push(VirtualNode::Element(el("li")));
+        let mut grown = el("ul");
+        grown.child_nodes.push(VirtualNode::Element(el("li")));
+        grown.child_nodes.push(VirtualNode::Element(el("li")));
+        grown.child_nodes.push(VirtualNode::Element(el("li")));
+
+        assert_eq!(
+            vec![Patch::AppendChild { path: vec![], node: VirtualNode::Element(el("li")) }],
+            diff(&VirtualNode::Element(old_el.clone()), &VirtualNode::Element(grown))
+        );
+
+        let mut shrunk = el("ul");
+        shrunk.child_nodes.push(VirtualNode::Element(el("li")));
+        assert_eq!(
+            vec![Patch::Truncate { path: vec![], len: 1 }],
+            diff(&VirtualNode::Element(old_el), &VirtualNode::Element(shrunk))
+        );
+    }
+
+    fn keyed(name: &str, key: &str) -> VirtualNode {
+        let mut el = el(name);
+        el.key = Some(key.to_string());
+        VirtualNode::Element(el)
+    }
+
+    fn list(children: Vec<VirtualNode>) -> VirtualNode {
+        let mut el = el("ul");
+        el.child_nodes = children;
+        VirtualNode::Element(el)
+    }
+
+    #[test]
+    fn keyed_front_insert_moves_one_child() {
+        let old = list(vec![keyed("li", "a"), keyed("li", "b"), keyed("li", "c")]);
+        let new = list(vec![keyed("li", "c"), keyed("li", "a"), keyed("li", "b")]);
+        assert_eq!(
+            vec![Patch::MoveChild { path: vec![], from: 2, to: 0 }],
+            diff(&old, &new)
+        );
+    }
+
+    #[test]
+    fn keyed_insert_new_child() {
+        let old = list(vec![keyed("li", "a"), keyed("li", "b")]);
+        let new = list(vec![keyed("li", "a"), keyed("li", "x"), keyed("li", "b")]);
+        assert_eq!(
+            vec![Patch::InsertChild { path: vec![], index: 1, node: keyed("li", "x") }],
+            diff(&old, &new)
+        );
+    }
+
+    fn keyed_text(name: &str, key: &str, text: &str) -> VirtualNode {
+        let mut el = el(name);
+        el.key = Some(key.to_string());
+        el.child_nodes.push(VirtualNode::Text(text.into()));
+        VirtualNode::Element(el)
+    }
+
+    #[test]
+    fn keyed_recurses_into_matched_children() {
+        let old = list(vec![keyed_text("li", "a", "old"), keyed_text("li", "b", "x")]);
+        let new = list(vec![keyed_text("li", "a", "NEW"), keyed_text("li", "b", "x")]);
+        assert_eq!(
+            vec![Patch::SetText { path: vec![0, 0], text: "NEW".into() }],
+            diff(&old, &new)
+        );
+    }
+
+    #[test]
+    fn keyed_move_still_carries_content_edit() {
+        let old = list(vec![keyed_text("li", "a", "x"), keyed_text("li", "b", "old")]);
+        let new = list(vec![keyed_text("li", "b", "new"), keyed_text("li", "a", "x")]);
+        // b moves to the front and its text changes; the move (structural) is
+        // emitted before the content edit, addressed by b's final slot.
+        assert_eq!(
+            vec![
+                Patch::MoveChild { path: vec![], from: 0, to: 1 },
+                Patch::SetText { path: vec![0, 0], text: "new".into() },
+            ],
+            diff(&old, &new)
+        );
+    }
+
+    // Apply a keyed patch list to a clone of `old`, in order, against one
+    // working tree; used to prove the emitted index spaces are self-consistent.
+    fn apply(mut root: VirtualNode, patches: &[Patch]) -> VirtualNode {
+        fn node_at<'a>(root: &'a mut VirtualNode, path: &[usize]) -> &'a mut VirtualNode {
+            match path.split_first() {
+                None => root,
+                Some((&head, rest)) => match root {
+                    VirtualNode::Element(el) => node_at(&mut el.child_nodes[head], rest),
+                    VirtualNode::Text(_) => unreachable!("path descends into a text node"),
+                },
+            }
+        }
+        for patch in patches {
+            match patch {
+                Patch::SetText { path, text } => {
+                    if let VirtualNode::Text(existing) = node_at(&mut root, path) {
+                        *existing = text.clone();
+                    }
+                }
+                Patch::RemoveChild { path, index } => {
+                    if let VirtualNode::Element(el) = node_at(&mut root, path) {
+                        el.child_nodes.remove(*index);
+                    }
+                }
+                Patch::InsertChild { path, index, node } => {
+                    if let VirtualNode::Element(el) = node_at(&mut root, path) {
+                        el.child_nodes.insert(*index, node.clone());
+                    }
+                }
+                Patch::MoveChild { path, from, to } => {
+                    if let VirtualNode::Element(el) = node_at(&mut root, path) {
+                        let node = el.child_nodes.remove(*from);
+                        el.child_nodes.insert(*to, node);
+                    }
+                }
+                _ => {}
+            }
+        }
+        root
+    }
+
+    fn keyed_list(keys: &[&str]) -> VirtualNode {
+        list(keys.iter().map(|k| keyed("li", k)).collect())
+    }
+
+    fn assert_reconciles(old_keys: &[&str], new_keys: &[&str]) {
+        let old = keyed_list(old_keys);
+        let new = keyed_list(new_keys);
+        let patches = diff(&old, &new);
+        assert_eq!(new, apply(old, &patches), "{:?} -> {:?}", old_keys, new_keys);
+    }
+
+    #[test]
+    fn keyed_patch_list_is_applicable() {
+        // Full reversal and rotation were the cases the positional index space
+        // got wrong; exercise them alongside removals and inserts.
+        assert_reconciles(&["a", "b", "c"], &["c", "b", "a"]);
+        assert_reconciles(&["a", "b", "c", "d"], &["d", "c", "b", "a"]);
+        assert_reconciles(&["a", "b", "c", "d"], &["b", "c", "d", "a"]);
+        assert_reconciles(&["a", "b", "c", "d"], &["d", "a", "b", "c"]);
+        assert_reconciles(&["a", "b", "c", "d"], &["x", "d", "a", "c"]);
+        assert_reconciles(&["a", "b", "c"], &["c", "x", "a", "y", "b"]);
+    }
+
+    #[test]
+    fn keyed_remove_missing_child() {
+        let old = list(vec![keyed("li", "a"), keyed("li", "b"), keyed("li", "c")]);
+        let new = list(vec![keyed("li", "a"), keyed("li", "c")]);
+        assert_eq!(
+            vec![Patch::RemoveChild { path: vec![], index: 1 }],
+            diff(&old, &new)
+        );
+    }
+
+    #[test]
+    fn diff_dom_zips_roots() {
+        let old = VirtualDom(vec![VirtualNode::Element(el("p"))]);
+        let new = VirtualDom(vec![
+            VirtualNode::Element(el("p")),
+            VirtualNode::Element(el("span")),
+        ]);
+        assert_eq!(
+            vec![Patch::AppendChild { path: vec![], node: VirtualNode::Element(el("span")) }],
+            diff_dom(&old, &new)
+        );
+    }
+}
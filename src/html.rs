@@ -0,0 +1,126 @@
+use {VirtualDom, VirtualElement, VirtualNode};
+
+/// Elements that are self-closing in HTML: they carry no children and get no
+/// closing tag.
+static VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Escape the characters that are unsafe to emit raw into markup or an
+/// attribute value.
+fn escape(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for ch in input.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+fn is_void(name: &str) -> bool {
+    VOID_ELEMENTS.contains(&name)
+}
+
+impl VirtualNode {
+    /// Serialize this node to an HTML string, escaping text content.
+    pub fn to_html(&self) -> String {
+        match self {
+            VirtualNode::Element(el) => el.to_html(),
+            VirtualNode::Text(text) => escape(text),
+        }
+    }
+}
+
+impl VirtualElement {
+    /// Serialize this element to `<name attr="val">children</name>`. Attributes
+    /// are emitted in sorted order so the output is stable for snapshot tests,
+    /// and void elements are rendered self-closing.
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        out.push('<');
+        out.push_str(&self.name);
+
+        let mut keys: Vec<&String> = self.attributes.keys().collect();
+        keys.sort();
+        for key in keys {
+            out.push(' ');
+            out.push_str(key);
+            out.push_str("=\"");
+            out.push_str(&escape(&self.attributes[key]));
+            out.push('"');
+        }
+        out.push('>');
+
+        if is_void(&self.name) {
+            return out;
+        }
+
+        for child in &self.child_nodes {
+            out.push_str(&child.to_html());
+        }
+        out.push_str("</");
+        out.push_str(&self.name);
+        out.push('>');
+        out
+    }
+}
+
+impl VirtualDom {
+    /// Serialize every root node in order, concatenated into one string.
+    pub fn to_html(&self) -> String {
+        self.0.iter().map(VirtualNode::to_html).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {VirtualDom, VirtualElement, VirtualNode};
+
+    #[test]
+    fn renders_element_with_sorted_attributes() {
+        let mut el = VirtualElement::new();
+        el.name = "a".to_string();
+        el.attributes.insert("href".into(), "/x".into());
+        el.attributes.insert("class".into(), "link".into());
+        el.child_nodes.push(VirtualNode::Text("go".into()));
+        assert_eq!("<a class=\"link\" href=\"/x\">go</a>", el.to_html());
+    }
+
+    #[test]
+    fn escapes_text_and_attribute_values() {
+        let mut el = VirtualElement::new();
+        el.name = "p".to_string();
+        el.attributes.insert("title".into(), "a\"b".into());
+        el.child_nodes.push(VirtualNode::Text("1 < 2 & 3 > 0".into()));
+        assert_eq!(
+            "<p title=\"a&quot;b\">1 &lt; 2 &amp; 3 &gt; 0</p>",
+            el.to_html()
+        );
+    }
+
+    #[test]
+    fn void_element_is_self_closing() {
+        let mut el = VirtualElement::new();
+        el.name = "br".to_string();
+        assert_eq!("<br>", el.to_html());
+    }
+
+    #[test]
+    fn fragment_concatenates_roots() {
+        let mut first = VirtualElement::new();
+        first.name = "p".to_string();
+        let mut second = VirtualElement::new();
+        second.name = "p".to_string();
+        let dom = VirtualDom(vec![
+            VirtualNode::Element(first),
+            VirtualNode::Element(second),
+        ]);
+        assert_eq!("<p></p><p></p>", dom.to_html());
+    }
+}
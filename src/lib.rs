@@ -0,0 +1,60 @@
+#[macro_use]
+pub mod template;
+
+pub mod diff;
+
+pub mod html;
+
+use std::collections::HashMap;
+
+/// A single element node in the virtual tree, e.g. `<div class="active">`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VirtualElement {
+    pub name: String,
+    pub attributes: HashMap<String, String>,
+    pub child_nodes: Vec<VirtualNode>,
+    /// An optional stable identity used by keyed reconciliation to track a
+    /// child across reorders instead of diffing it positionally.
+    pub key: Option<String>,
+}
+
+impl VirtualElement {
+    pub fn new() -> VirtualElement {
+        VirtualElement {
+            name: "div".to_string(),
+            attributes: HashMap::new(),
+            child_nodes: Vec::new(),
+            key: None,
+        }
+    }
+}
+
+impl Default for VirtualElement {
+    fn default() -> VirtualElement {
+        VirtualElement::new()
+    }
+}
+
+/// Either an element or a run of text; the two things a tree is built from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VirtualNode {
+    Element(VirtualElement),
+    Text(String),
+}
+
+/// A list of root nodes. Templates evaluate to one of these so that a single
+/// template can describe either one root or several siblings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VirtualDom(pub Vec<VirtualNode>);
+
+impl From<VirtualElement> for VirtualDom {
+    fn from(element: VirtualElement) -> VirtualDom {
+        VirtualDom(vec![VirtualNode::Element(element)])
+    }
+}
+
+impl<T: ToString> From<T> for VirtualDom {
+    fn from(value: T) -> VirtualDom {
+        VirtualDom(vec![VirtualNode::Text(value.to_string())])
+    }
+}
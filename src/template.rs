@@ -1,10 +1,16 @@
 #[macro_export]
 macro_rules! template {
+    () => ({
+        $crate::VirtualDom(Vec::<$crate::VirtualNode>::new())
+    });
     ($($inner:tt)*) => ({
         let mut el = $crate::VirtualElement::new();
-        // "+" is disallowed at the top level, so no sibling elements will be returned
-        let _ = inner_template!(top_level, $($inner)*)(&mut el);
-        el
+        // Any "+" siblings are threaded back out as the returned vector; the
+        // first element is always "el" itself, so collect both into a fragment.
+        let mut siblings = inner_template!(top_level, $($inner)*)(&mut el);
+        let mut nodes = vec![$crate::VirtualNode::Element(el)];
+        nodes.append(&mut siblings);
+        $crate::VirtualDom(nodes)
     });
 }
 
@@ -26,6 +32,17 @@ macro_rules! inner_template {
 
         Vec::<$crate::VirtualNode>::new()
     });
+    ($tl:ident, >$($comp:ident)::+($($args:tt)*)$($inner:tt)*) => (|el: &mut $crate::VirtualElement| {
+        // A component call: invoke the (possibly path-qualified) function and
+        // splice every root node it returns into the parent, exactly as a
+        // parenthesized group is handled.
+        el.child_nodes.append(&mut $crate::VirtualDom::from($($comp)::+($($args)*)).0);
+
+        let mut el_remaining_siblings = inner_template!(not_top_level, $($inner)*)(el);
+        el.child_nodes.append(&mut el_remaining_siblings);
+
+        Vec::<$crate::VirtualNode>::new()
+    });
     ($tl:ident, >$($inner:tt)*) => (|el: &mut $crate::VirtualElement| {
         let mut el_remaining = $crate::VirtualElement::new();
         let mut el_remaining_siblings = inner_template!(not_top_level, $($inner)*)(&mut el_remaining);
@@ -34,7 +51,7 @@ macro_rules! inner_template {
 
         Vec::<$crate::VirtualNode>::new()
     });
-    (not_top_level, +($($inner_parens:tt)*)$($inner:tt)*) => (|el: &mut $crate::VirtualElement| {
+    ($tl:ident, +($($inner_parens:tt)*)$($inner:tt)*) => (|el: &mut $crate::VirtualElement| {
         let mut el_parens = $crate::VirtualElement::new();
         let mut el_parens_siblings = inner_template!(not_top_level, $($inner)*)(&mut el_parens);
 
@@ -47,7 +64,7 @@ macro_rules! inner_template {
         els.append(&mut el_remaining_siblings);
         els
     });
-    (not_top_level, +$($inner:tt)*) => (|_: &mut $crate::VirtualElement| {
+    ($tl:ident, +$($inner:tt)*) => (|_: &mut $crate::VirtualElement| {
         let mut el_remaining = $crate::VirtualElement::new();
         let mut el_remaining_siblings =
             inner_template!(not_top_level, $($inner)*)(&mut el_remaining);
@@ -83,19 +100,23 @@ macro_rules! inner_template {
 
 #[cfg(test)]
 mod tests {
+    fn dom(el: ::VirtualElement) -> ::VirtualDom {
+        ::VirtualDom(vec![::VirtualNode::Element(el)])
+    }
+
     #[test]
     fn template_name_class_id() {
         let mut el = ::VirtualElement::new();
-        assert_eq!(el, template!(div));
+        assert_eq!(dom(el.clone()), template!(div));
 
         el.name = "a".to_string();
-        assert_eq!(el, template!(a));
+        assert_eq!(dom(el.clone()), template!(a));
 
         el.attributes.insert("class".into(), "active red".into());
-        assert_eq!(el, template!(a.active.red));
+        assert_eq!(dom(el.clone()), template!(a.active.red));
 
         el.attributes.insert("id".into(), "main".into());
-        assert_eq!(el, template!(a#main.active.red));
+        assert_eq!(dom(el), template!(a#main.active.red));
     }
 
     #[test]
@@ -103,21 +124,21 @@ mod tests {
         let mut el = ::VirtualElement::new();
         el.child_nodes.push(::VirtualNode::Text("some inner text".into()));
         el.child_nodes.push(::VirtualNode::Text("4".into()));
-        assert_eq!(el, template!(div{"some inner text"}{1 + 3}));
+        assert_eq!(dom(el), template!(div{"some inner text"}{1 + 3}));
     }
 
     #[test]
     fn template_bind_attribute() {
         let mut el = ::VirtualElement::new();
         el.attributes.insert("width".into(), "44".into());
-        assert_eq!(el, template!(div[width={40 + 4}]));
+        assert_eq!(dom(el), template!(div[width={40 + 4}]));
     }
 
     #[test]
     fn template_child_nodes () {
         let mut el = ::VirtualElement::new();
         el.child_nodes.push(::VirtualNode::Element(::VirtualElement::new()));
-        assert_eq!(el, template!(div>div));
+        assert_eq!(dom(el), template!(div>div));
     }
 
     #[test]
@@ -125,7 +146,7 @@ mod tests {
         let mut el = ::VirtualElement::new();
         el.child_nodes.push(::VirtualNode::Element(::VirtualElement::new()));
         el.child_nodes.push(::VirtualNode::Element(::VirtualElement::new()));
-        assert_eq!(el, template!(div>div+div));
+        assert_eq!(dom(el), template!(div>div+div));
     }
 
     #[test]
@@ -137,6 +158,69 @@ mod tests {
         el.child_nodes.push(::VirtualNode::Element(group_el));
         el.child_nodes.push(::VirtualNode::Element(::VirtualElement::new()));
 
-        assert_eq!(el, template!(div>(div>div)+(div)));
+        assert_eq!(dom(el), template!(div>(div>div)+(div)));
+    }
+
+    fn labelled(label: &str) -> ::VirtualElement {
+        let mut el = ::VirtualElement::new();
+        el.name = "span".to_string();
+        el.child_nodes.push(::VirtualNode::Text(label.into()));
+        el
+    }
+
+    fn two_items() -> ::VirtualDom {
+        template!(li{"a"} + li{"b"})
+    }
+
+    mod widgets {
+        pub fn badge() -> ::VirtualElement {
+            let mut el = ::VirtualElement::new();
+            el.name = "b".to_string();
+            el
+        }
+    }
+
+    #[test]
+    fn template_component_call () {
+        let mut el = ::VirtualElement::new();
+        el.child_nodes.push(::VirtualNode::Element(labelled("hi")));
+        assert_eq!(dom(el), template!(div > labelled("hi")));
+    }
+
+    #[test]
+    fn template_component_path_call () {
+        let mut el = ::VirtualElement::new();
+        el.child_nodes.push(::VirtualNode::Element(widgets::badge()));
+        assert_eq!(dom(el), template!(div > widgets::badge()));
+    }
+
+    #[test]
+    fn template_component_returning_fragment () {
+        let mut el = ::VirtualElement::new();
+        el.name = "ul".to_string();
+        el.child_nodes = two_items().0;
+        assert_eq!(dom(el), template!(ul > two_items()));
+    }
+
+    #[test]
+    fn template_empty_fragment () {
+        assert_eq!(::VirtualDom(Vec::new()), template!{});
+    }
+
+    #[test]
+    fn template_root_siblings () {
+        let mut first = ::VirtualElement::new();
+        first.name = "p".to_string();
+        first.child_nodes.push(::VirtualNode::Text("a".into()));
+
+        let mut second = ::VirtualElement::new();
+        second.name = "p".to_string();
+        second.child_nodes.push(::VirtualNode::Text("b".into()));
+
+        let expected = ::VirtualDom(vec![
+            ::VirtualNode::Element(first),
+            ::VirtualNode::Element(second),
+        ]);
+        assert_eq!(expected, template!(p{"a"} + p{"b"}));
     }
 }